@@ -2,7 +2,12 @@ use float_ord::FloatOrd;
 use gloo::storage::{LocalStorage, Storage};
 use itertools::Itertools;
 use js_sys::Date;
-use mindsweeper::{analyzer::Analyzer, server::*, utils::*};
+use mindsweeper::{
+    analyzer::Analyzer,
+    hint::{HintLadder, HintLevel},
+    server::*,
+    utils::*,
+};
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 use strum::{Display, EnumIter, IntoEnumIterator};
@@ -33,6 +38,7 @@ pub enum Msg {
     ShowDialog,
     CloseDialog,
     NewGame,
+    Hint,
     SetGridConfig(GridConfig),
     SetGameMode(GameMode),
     SetPunishGuessing(bool),
@@ -91,6 +97,7 @@ pub struct Client<Game: Oracle> {
     last_revealed: Vec<usize>,
     controls_swapped: bool,
     touching_tile: Option<TileTouch>,
+    hint: HintLadder,
 }
 
 mod storage_keys {
@@ -98,6 +105,7 @@ mod storage_keys {
     pub static THEME: &str = "theme";
     pub static CLOSED_DIALOG: &str = "closed_dialog";
     pub static BEST_TIMES: &str = "best_times";
+    pub static HINTED_BEST_TIMES: &str = "hinted_best_times";
 }
 
 struct PreparedGame<Game: Oracle> {
@@ -207,6 +215,7 @@ impl<Game: Oracle> Client<Game> {
                 self.last_revealed.push(tile_id);
             }
         }
+        self.hint.reset();
         let mut tentative_flag_ids = Vec::new();
         for (id, tile) in game.iter_adjacent_mine_counts().enumerate() {
             match tile {
@@ -258,6 +267,7 @@ impl<Game: Oracle> Client<Game> {
             return;
         };
         let mut new_flag_ids = array_vec!([usize; 8]);
+        let flag_count = self.flags.len();
         match game.adjacent_mine_count(tile_id) {
             Some(adjacent_mine_count) => {
                 if self.game_config.mode != GameMode::Autopilot {
@@ -290,6 +300,9 @@ impl<Game: Oracle> Client<Game> {
                 }
             }
         }
+        if self.flags.len() != flag_count {
+            self.hint.reset();
+        }
         if self.game_config.mode == GameMode::Autopilot {
             // trigger autopilot by chording around new tentative flags
             let mut tiles_to_click = Vec::new();
@@ -311,6 +324,41 @@ impl<Game: Oracle> Client<Game> {
         self.flags.clear();
         self.last_revealed.clear();
         self.controls_swapped = false;
+        self.hint = HintLadder::new();
+    }
+
+    fn escalate_hint(&mut self) {
+        if let Some(game) = self.game.as_ref().filter(|game| game.status().is_ongoing()) {
+            self.hint
+                .escalate(game, |tile_id| self.flags.contains(tile_id));
+        }
+    }
+
+    fn hint_message(&self) -> Option<String> {
+        let game = self.game.as_ref()?;
+        if !game.status().is_ongoing() {
+            return None;
+        }
+        if self.hint.found_nothing() {
+            return Some("No deduction available — a guess is needed.".to_string());
+        }
+        let (level, deduction) = self.hint.current()?;
+        let thing = if deduction.target_is_mine {
+            "mine"
+        } else {
+            "safe tile"
+        };
+        Some(match level {
+            HintLevel::Region => {
+                format!("There's a {thing} you can find in the highlighted region.")
+            }
+            HintLevel::Support if deduction.support_tile_ids.is_empty() => {
+                format!("Counting the remaining mines is enough to find a {thing}.")
+            }
+            HintLevel::Support => format!("The highlighted numbers are enough to find a {thing}."),
+            HintLevel::Target => format!("The marked tile is a {thing}."),
+            HintLevel::Explanation => deduction.explain(game),
+        })
     }
 
     fn view_tile(&self, tile_id: usize, analyzer: Option<&Analyzer>, scope: &Scope<Self>) -> Html {
@@ -412,6 +460,23 @@ impl<Game: Oracle> Client<Game> {
                     }
                 }
             }
+            if let Some((level, deduction)) =
+                self.hint.current().filter(|_| game.status().is_ongoing())
+            {
+                if deduction.region_tile_ids.contains(&tile_id) {
+                    tile_classes.push("hint-region");
+                }
+                if level >= HintLevel::Support && deduction.support_tile_ids.contains(&tile_id) {
+                    tile_classes.push("hint-support");
+                }
+                if level >= HintLevel::Target && deduction.target_tile_id == tile_id {
+                    tile_classes.push(if deduction.target_is_mine {
+                        "hint-mine"
+                    } else {
+                        "hint-safe"
+                    });
+                }
+            }
         }
 
         tile_classes.extend(bg_class);
@@ -440,6 +505,12 @@ impl<Game: Oracle> Client<Game> {
         }
     }
 
+    fn provenance(&self) -> BoardProvenance {
+        self.game
+            .as_ref()
+            .map_or(BoardProvenance::FreshlyGenerated, Game::provenance)
+    }
+
     fn remaining_flag_count(&self) -> isize {
         match &self.game {
             Some(game) if game.status().is_won() => 0,
@@ -476,6 +547,7 @@ impl<Game: Oracle> Component for Client<Game> {
             last_revealed: vec![],
             controls_swapped: false,
             touching_tile: None,
+            hint: HintLadder::new(),
         }
     }
 
@@ -549,6 +621,7 @@ impl<Game: Oracle> Component for Client<Game> {
             Msg::ShowDialog => self.show_dialog(),
             Msg::CloseDialog => self.close_dialog(),
             Msg::NewGame => self.new_game(),
+            Msg::Hint => self.escalate_hint(),
             Msg::SetGridConfig(config) => {
                 self.game_config.grid_config = config;
                 self.save_game_config();
@@ -597,6 +670,7 @@ impl<Game: Oracle> Component for Client<Game> {
                 analyzer
             })
         });
+        let hint_message = self.hint_message();
         let stop_propagation = |e: MouseEvent| e.stop_propagation();
         self.update_css_board_width();
         html! {<>
@@ -715,6 +789,9 @@ impl<Game: Oracle> Component for Client<Game> {
                                             <em> { "does" } </em>
                                             { " contain a mine. When enabled, you may not make deductions based on the no-guessing-needed property." }
                                         </li>
+                                        <li>
+                                            { "Boards with a fixed layout are never punished, since that would change their layout. A badge above the board shows when this applies." }
+                                        </li>
                                     </ul>
                                 </li>
                             </ul>
@@ -800,6 +877,8 @@ impl<Game: Oracle> Component for Client<Game> {
                 <Timer
                     show_timer={self.theme.show_timer}
                     game_config={self.game_config}
+                    hint_depth={self.hint.deepest_level()}
+                    provenance={self.provenance()}
                     timer_mode={
                         match self.game.as_ref().map(Game::status) {
                             None => TimerMode::Reset,
@@ -808,6 +887,11 @@ impl<Game: Oracle> Component for Client<Game> {
                             Some(GameStatus::Lost) => TimerMode::Stopped { won_game: false },
                         }
                     }/>
+                <span
+                    class={classes!("badge", (!self.provenance().is_fixed_layout()).then_some("hidden"))}
+                    title="This board's layout is fixed, so guesses are not punished, and wins don't count toward best times.">
+                    { "fixed board — guesses not punished" }
+                </span>
                 <span>
                     { "Safe: " }
                     { self.game
@@ -823,7 +907,9 @@ impl<Game: Oracle> Component for Client<Game> {
                 <table
                     class={classes!(
                         self.controls_swapped.then_some("controls-swapped"),
-                        self.game_config.punish_guessing.then_some("punish-guessing"),
+                        self.provenance()
+                            .punishes_guessing(self.game_config)
+                            .then_some("punish-guessing"),
                         match self.game_config.mode {
                             GameMode::Normal => None,
                             GameMode::Autopilot => Some("autopilot"),
@@ -861,11 +947,27 @@ impl<Game: Oracle> Component for Client<Game> {
                         }
                     }
                 </button>
+                <button onclick={scope.callback(|_| Msg::Hint)}
+                        disabled={self.game.is_none()
+                            || analyzer.is_some()
+                            || self.hint.current().is_some_and(|(level, _)| level.next().is_none())}>
+                    { "Hint" }
+                    {
+                        if let Some((level, _)) = self.hint.current() {
+                            format!(" ({}/{})", level.number(), HintLevel::count())
+                        } else {
+                            String::new()
+                        }
+                    }
+                </button>
                 <button onclick={scope.callback(|_| Msg::NewGame)}
                         disabled={self.game.is_none()}>
                     { "New Game" }
                 </button>
             </div>
+            <p id="hint" class={hint_message.is_none().then_some("hidden")}>
+                { hint_message }
+            </p>
         </>}
     }
 }
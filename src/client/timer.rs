@@ -5,7 +5,10 @@ use gloo::{
 };
 use itertools::Itertools;
 use js_sys::Date;
-use mindsweeper::server::GameConfig;
+use mindsweeper::{
+    hint::HintLevel,
+    server::{BoardProvenance, GameConfig, RecordBucket},
+};
 use std::{collections::BTreeMap, fmt};
 use yew::prelude::*;
 
@@ -20,6 +23,9 @@ pub enum TimerMode {
 pub struct TimerProps {
     pub show_timer: ShowTimer,
     pub game_config: GameConfig,
+    /// Deepest hint level used in the current game, if any
+    pub hint_depth: Option<HintLevel>,
+    pub provenance: BoardProvenance,
     pub timer_mode: TimerMode,
 }
 
@@ -27,7 +33,8 @@ pub struct Timer {
     start_date: Option<Date>,
     stop_date: Option<Date>,
     interval: Option<Interval>,
-    best_times: BTreeMap<GameConfig, f64>,
+    /// Best times are kept separately for each hint depth, so hinted wins never displace unhinted ones
+    best_times: BTreeMap<RecordBucket, f64>,
 }
 
 pub enum TimerMsg {
@@ -43,6 +50,55 @@ impl Timer {
         };
         elapsed_ms / 1000.0
     }
+
+    /// The best time that a win under these props would be compared against, if it counts toward one at all
+    fn best_time(&self, props: &TimerProps) -> Option<f64> {
+        props
+            .provenance
+            .record_bucket(props.game_config, props.hint_depth)
+            .and_then(|key| self.best_times.get(&key))
+            .copied()
+    }
+
+    /// Records a win under these props, returning whether it set a new best time (in which case the caller should save)
+    fn record_win(&mut self, props: &TimerProps, time: f64) -> bool {
+        let Some(key) = props
+            .provenance
+            .record_bucket(props.game_config, props.hint_depth)
+        else {
+            return false;
+        };
+        if self.best_times.get(&key).is_some_and(|&best| best <= time) {
+            return false;
+        }
+        self.best_times.insert(key, time);
+        true
+    }
+
+    fn save_best_times(&self) {
+        let (unhinted, hinted): (Vec<_>, Vec<_>) = self
+            .best_times
+            .iter()
+            .partition(|((_, hint_depth), _)| hint_depth.is_none());
+        LocalStorage::set(
+            storage_keys::BEST_TIMES,
+            unhinted
+                .into_iter()
+                .map(|((game_config, _), time)| (game_config, time))
+                .collect_vec(),
+        )
+        .unwrap_or_default();
+        LocalStorage::set(
+            storage_keys::HINTED_BEST_TIMES,
+            hinted
+                .into_iter()
+                .filter_map(|((game_config, hint_depth), time)| {
+                    Some(((game_config, (*hint_depth)?), time))
+                })
+                .collect_vec(),
+        )
+        .unwrap_or_default();
+    }
 }
 
 struct TimerElapsed(f64);
@@ -73,9 +129,20 @@ impl Component for Timer {
             start_date: None,
             stop_date: None,
             interval: None,
-            best_times: LocalStorage::get::<Vec<_>>(storage_keys::BEST_TIMES)
+            best_times: LocalStorage::get::<Vec<(GameConfig, f64)>>(storage_keys::BEST_TIMES)
                 .unwrap_or_default()
                 .into_iter()
+                .map(|(game_config, time)| ((game_config, None), time))
+                .chain(
+                    LocalStorage::get::<Vec<((GameConfig, HintLevel), f64)>>(
+                        storage_keys::HINTED_BEST_TIMES,
+                    )
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|((game_config, hint_depth), time)| {
+                        ((game_config, Some(hint_depth)), time)
+                    }),
+                )
                 .collect(),
         }
     }
@@ -85,6 +152,8 @@ impl Component for Timer {
         if old_props.timer_mode == new_props.timer_mode {
             return new_props.timer_mode == TimerMode::Running
                 || old_props.game_config != new_props.game_config
+                || old_props.hint_depth != new_props.hint_depth
+                || old_props.provenance != new_props.provenance
                 || old_props.show_timer != new_props.show_timer;
         }
         match new_props.timer_mode {
@@ -103,20 +172,8 @@ impl Component for Timer {
             TimerMode::Stopped { won_game } => {
                 self.stop_date = Some(Date::new_0());
                 self.interval.take().map(Interval::cancel);
-                if won_game {
-                    let time = self.elapsed_secs();
-                    if self
-                        .best_times
-                        .get(&new_props.game_config)
-                        .map_or(true, |&best| time < best)
-                    {
-                        self.best_times.insert(new_props.game_config, time);
-                        LocalStorage::set(
-                            storage_keys::BEST_TIMES,
-                            self.best_times.iter().collect_vec(),
-                        )
-                        .unwrap_or_default();
-                    }
+                if won_game && self.record_win(new_props, self.elapsed_secs()) {
+                    self.save_best_times();
                 }
             }
         }
@@ -128,7 +185,7 @@ impl Component for Timer {
         if props.show_timer == ShowTimer::Never {
             return html! {};
         }
-        let best = self.best_times.get(&props.game_config).copied();
+        let best = self.best_time(props);
         let mut timer_classes = classes!("timer");
         let time = if props.timer_mode == TimerMode::Reset {
             timer_classes.push("text-faded");
@@ -147,13 +204,85 @@ impl Component for Timer {
             Some(time)
         };
         html! {
-            <span class={timer_classes}>
+            <span class={timer_classes}
+                  title={props.hint_depth.map(|depth| format!("Hints used, up to level {}", depth.number()))}>
                 { if let Some(time) = time {
                     html! { <> { TimerElapsed(time) } </> }
                 } else {
                     html! { <> { "--:--.--" } </> }
                 } }
+                { props.hint_depth.map(|depth| format!(" 💡{}", depth.number())) }
             </span>
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mindsweeper::server::FixedLayoutReason;
+    use strum::IntoEnumIterator;
+
+    fn timer() -> Timer {
+        Timer {
+            start_date: None,
+            stop_date: None,
+            interval: None,
+            best_times: BTreeMap::new(),
+        }
+    }
+
+    fn won_props(hint_depth: Option<HintLevel>, provenance: BoardProvenance) -> TimerProps {
+        TimerProps {
+            show_timer: ShowTimer::Always,
+            game_config: GameConfig::default(),
+            hint_depth,
+            provenance,
+            timer_mode: TimerMode::Stopped { won_game: true },
+        }
+    }
+
+    #[test]
+    fn fixed_layout_wins_are_not_recorded() {
+        let mut timer = timer();
+        for reason in FixedLayoutReason::iter() {
+            let props = won_props(None, BoardProvenance::FixedLayout { reason });
+            assert!(!timer.record_win(&props, 1.0));
+            assert_eq!(timer.best_time(&props), None);
+        }
+        assert!(timer.best_times.is_empty());
+    }
+
+    #[test]
+    fn fresh_wins_are_recorded_per_hint_depth() {
+        let mut timer = timer();
+        let unhinted = won_props(None, BoardProvenance::FreshlyGenerated);
+        let hinted = won_props(Some(HintLevel::Support), BoardProvenance::FreshlyGenerated);
+
+        assert!(timer.record_win(&hinted, 5.0));
+        assert_eq!(
+            timer.best_times,
+            BTreeMap::from([((GameConfig::default(), Some(HintLevel::Support)), 5.0)])
+        );
+        assert_eq!(timer.best_time(&hinted), Some(5.0));
+        // a hinted win never counts toward the unhinted best time
+        assert_eq!(timer.best_time(&unhinted), None);
+
+        assert!(timer.record_win(&unhinted, 7.0));
+        assert!(!timer.record_win(&unhinted, 8.0));
+        assert!(timer.record_win(&unhinted, 6.0));
+        assert_eq!(timer.best_time(&unhinted), Some(6.0));
+        assert_eq!(timer.best_time(&hinted), Some(5.0));
+
+        // the same layout, once fixed, shares neither bucket
+        let fixed = won_props(
+            None,
+            BoardProvenance::FixedLayout {
+                reason: FixedLayoutReason::Predetermined,
+            },
+        );
+        assert!(!timer.record_win(&fixed, 1.0));
+        assert_eq!(timer.best_time(&fixed), None);
+        assert_eq!(timer.best_time(&unhinted), Some(6.0));
+    }
+}
@@ -0,0 +1,812 @@
+use crate::{
+    analyzer::Analyzer,
+    server::{GridConfig, Oracle},
+};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use tinyvec::ArrayVec;
+
+/// Deductions supported by at most this many number tiles are searched for directly, before falling back to the analyzer
+const MAX_LOCAL_SUPPORT_SIZE: usize = 3;
+
+/// Explanations name at most this many numbers, beyond which they refer to the highlighted numbers as a whole
+const MAX_NAMED_NUMBERS: usize = 4;
+
+/// How much a hint gives away, from least to most
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum HintLevel {
+    /// Highlights the region containing a deduction, without marking any tile
+    Region,
+    /// Highlights the number tiles that the deduction relies on
+    Support,
+    /// Marks the tile that can be deduced to be safe or a mine
+    Target,
+    /// Explains the deduction in words
+    Explanation,
+}
+
+impl HintLevel {
+    pub fn next(self) -> Option<Self> {
+        match self {
+            HintLevel::Region => Some(HintLevel::Support),
+            HintLevel::Support => Some(HintLevel::Target),
+            HintLevel::Target => Some(HintLevel::Explanation),
+            HintLevel::Explanation => None,
+        }
+    }
+
+    /// 1-based position on the ladder, for display
+    pub fn number(self) -> usize {
+        self as usize + 1
+    }
+
+    pub const fn count() -> usize {
+        HintLevel::Explanation as usize + 1
+    }
+}
+
+/// How a deduction was found, from simplest to most involved
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Reasoning {
+    /// Follows from a few supporting number tiles
+    Local,
+    /// Follows from the numbers around the target's component, which are more than the local search considers
+    Component,
+    /// Additionally requires the total mine count
+    MineCount,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Deduction {
+    /// Hidden and number tiles of the frontier component containing the target
+    pub region_tile_ids: BTreeSet<usize>,
+    /// Number tiles that the deduction relies on (always a subset of the region)
+    pub support_tile_ids: BTreeSet<usize>,
+    /// Hidden tile whose contents can be deduced (always inside the region)
+    pub target_tile_id: usize,
+    pub target_is_mine: bool,
+    pub reasoning: Reasoning,
+}
+
+impl Deduction {
+    /// Smaller is better: simpler reasoning first, then few supporting numbers, then safe tiles before mines
+    fn rank(&self) -> (Reasoning, usize, bool, usize) {
+        (
+            self.reasoning,
+            self.support_tile_ids.len(),
+            self.target_is_mine,
+            self.target_tile_id,
+        )
+    }
+
+    pub fn explain(&self, game: &impl Oracle) -> String {
+        let grid_config = game.config().grid_config;
+        let target = describe_position(grid_config, self.target_tile_id);
+        let conclusion = if self.target_is_mine {
+            format!("has a mine at {target}")
+        } else {
+            format!("leaves {target} safe")
+        };
+        let numbers = if self.support_tile_ids.len() > MAX_NAMED_NUMBERS {
+            format!("the {} highlighted numbers", self.support_tile_ids.len())
+        } else {
+            join_with_and(
+                &self
+                    .support_tile_ids
+                    .iter()
+                    .map(|&tile_id| {
+                        let count = game
+                            .adjacent_mine_count(tile_id)
+                            .expect("supporting tile should be revealed");
+                        format!("the {count} at {}", describe_position(grid_config, tile_id))
+                    })
+                    .collect_vec(),
+            )
+        };
+        let mine_count = grid_config.mine_count();
+        match self.reasoning {
+            Reasoning::Local | Reasoning::Component => {
+                format!("Every arrangement of mines that satisfies {numbers} {conclusion}.")
+            }
+            Reasoning::MineCount if self.support_tile_ids.is_empty() => {
+                if self.target_is_mine {
+                    format!("There are too many mines left for all of them to be next to numbers, so {target} must be a mine.")
+                } else {
+                    format!("Every arrangement of mines that satisfies the numbers already uses all {mine_count} mines, so {target} must be safe.")
+                }
+            }
+            Reasoning::MineCount => format!(
+                "Every arrangement of mines that satisfies {numbers}, along with the total of {mine_count} mines, {conclusion}."
+            ),
+        }
+    }
+}
+
+fn describe_position(grid_config: GridConfig, tile_id: usize) -> String {
+    format!(
+        "row {}, column {}",
+        tile_id / grid_config.width() + 1,
+        tile_id % grid_config.width() + 1
+    )
+}
+
+fn join_with_and(items: &[String]) -> String {
+    match items {
+        [] => String::new(),
+        [item] => item.clone(),
+        [rest @ .., last] => format!("{} and {last}", rest.join(", ")),
+    }
+}
+
+struct Constraint {
+    number_tile_id: usize,
+    mine_count: u8,
+    hidden_tile_ids: ArrayVec<[usize; 8]>,
+}
+
+fn constraints(game: &impl Oracle) -> Vec<Constraint> {
+    let grid_config = game.config().grid_config;
+    game.iter_adjacent_mine_counts()
+        .enumerate()
+        .filter_map(|(number_tile_id, tile)| {
+            let mine_count = tile?;
+            let hidden_tile_ids: ArrayVec<[usize; 8]> = grid_config
+                .iter_adjacent(number_tile_id)
+                .filter(|&id| game.adjacent_mine_count(id).is_none())
+                .collect();
+            (!hidden_tile_ids.is_empty()).then_some(Constraint {
+                number_tile_id,
+                mine_count,
+                hidden_tile_ids,
+            })
+        })
+        .collect()
+}
+
+/// Returns every mine arrangement of `hidden_tile_ids` (as a bitmask over its indices) satisfying the given constraints
+fn enumerate_arrangements(constraints: &[&Constraint], hidden_tile_ids: &[usize]) -> Vec<u32> {
+    fn helper(
+        constraints: &[&Constraint],
+        hidden_tile_ids: &[usize],
+        index: usize,
+        mask: u32,
+        arrangements: &mut Vec<u32>,
+    ) {
+        let satisfiable = constraints.iter().all(|constraint| {
+            let (mut assigned_mine_count, mut unassigned_count) = (0, 0);
+            for tile_id in &constraint.hidden_tile_ids {
+                let i = hidden_tile_ids.binary_search(tile_id).unwrap();
+                if i >= index {
+                    unassigned_count += 1;
+                } else if mask & 1 << i != 0 {
+                    assigned_mine_count += 1;
+                }
+            }
+            assigned_mine_count <= constraint.mine_count
+                && assigned_mine_count + unassigned_count >= constraint.mine_count
+        });
+        if !satisfiable {
+            return;
+        }
+        if index == hidden_tile_ids.len() {
+            arrangements.push(mask);
+            return;
+        }
+        helper(constraints, hidden_tile_ids, index + 1, mask, arrangements);
+        helper(
+            constraints,
+            hidden_tile_ids,
+            index + 1,
+            mask | 1 << index,
+            arrangements,
+        );
+    }
+
+    let mut arrangements = Vec::new();
+    helper(constraints, hidden_tile_ids, 0, 0, &mut arrangements);
+    arrangements
+}
+
+/// Finds deductions that follow from some small connected set of number tiles, preferring the smallest such sets. Flagged tiles are never targeted, since the player has already dealt with them.
+fn find_local_deductions(
+    constraints: &[Constraint],
+    is_flagged: impl Fn(usize) -> bool,
+) -> Vec<(BTreeSet<usize>, usize, bool)> {
+    let mut constraint_ids_by_hidden_tile_id = BTreeMap::<usize, Vec<usize>>::new();
+    for (i, constraint) in constraints.iter().enumerate() {
+        for &tile_id in &constraint.hidden_tile_ids {
+            constraint_ids_by_hidden_tile_id
+                .entry(tile_id)
+                .or_default()
+                .push(i);
+        }
+    }
+
+    let mut subsets: BTreeSet<Vec<usize>> = (0..constraints.len()).map(|i| vec![i]).collect();
+    for size in 1..=MAX_LOCAL_SUPPORT_SIZE {
+        if size > 1 {
+            subsets = subsets
+                .iter()
+                .flat_map(|subset| {
+                    subset
+                        .iter()
+                        .flat_map(|&i| &constraints[i].hidden_tile_ids)
+                        .flat_map(|tile_id| &constraint_ids_by_hidden_tile_id[tile_id])
+                        .filter(|j| !subset.contains(j))
+                        .map(|&j| {
+                            let mut grown = subset.clone();
+                            grown.push(j);
+                            grown.sort_unstable();
+                            grown
+                        })
+                        .collect_vec()
+                })
+                .collect();
+        }
+        let mut deductions = Vec::new();
+        for subset in &subsets {
+            let subset_constraints = subset.iter().map(|&i| &constraints[i]).collect_vec();
+            let hidden_tile_ids = subset_constraints
+                .iter()
+                .flat_map(|constraint| constraint.hidden_tile_ids)
+                .sorted_unstable()
+                .dedup()
+                .collect_vec();
+            let arrangements = enumerate_arrangements(&subset_constraints, &hidden_tile_ids);
+            let may_be_mine = arrangements.iter().fold(0, |acc, mask| acc | mask);
+            let may_be_safe = arrangements.iter().fold(0, |acc, mask| acc | !mask);
+            for (i, &tile_id) in hidden_tile_ids.iter().enumerate() {
+                let bit = 1 << i;
+                if (may_be_mine ^ may_be_safe) & bit != 0 && !is_flagged(tile_id) {
+                    let support = subset
+                        .iter()
+                        .map(|&i| constraints[i].number_tile_id)
+                        .collect();
+                    deductions.push((support, tile_id, may_be_mine & bit != 0));
+                }
+            }
+        }
+        if !deductions.is_empty() {
+            return deductions;
+        }
+    }
+    Vec::new()
+}
+
+/// Returns the frontier component containing the given hidden tile: every hidden tile connected to it through shared adjacent number tiles, along with those number tiles. Unlike `Analyzer::partition`, this treats every hidden tile as unknown, since that's what the player sees.
+///
+/// If the tile has no adjacent number tiles, then every other hidden tile without adjacent number tiles is returned instead, since those are all in the same situation.
+fn frontier_component(game: &impl Oracle, hidden_tile_id: usize) -> BTreeSet<usize> {
+    let grid_config = game.config().grid_config;
+    let is_hidden = |id: usize| game.adjacent_mine_count(id).is_none();
+    let is_unconstrained =
+        |id: usize| is_hidden(id) && grid_config.iter_adjacent(id).all(is_hidden);
+
+    if is_unconstrained(hidden_tile_id) {
+        return (0..grid_config.tile_count())
+            .filter(|&id| is_unconstrained(id))
+            .collect();
+    }
+
+    let mut component = BTreeSet::from([hidden_tile_id]);
+    let mut pending_hidden_tile_ids = vec![hidden_tile_id];
+    while let Some(id) = pending_hidden_tile_ids.pop() {
+        for number_tile_id in grid_config.iter_adjacent(id).filter(|&id| !is_hidden(id)) {
+            if !component.insert(number_tile_id) {
+                continue;
+            }
+            for adjacent_hidden_tile_id in grid_config
+                .iter_adjacent(number_tile_id)
+                .filter(|&id| is_hidden(id))
+            {
+                if component.insert(adjacent_hidden_tile_id) {
+                    pending_hidden_tile_ids.push(adjacent_hidden_tile_id);
+                }
+            }
+        }
+    }
+    component
+}
+
+/// Picks a good deduction to hint about, preferring those that rely on the fewest number tiles. Flagged tiles are skipped, but flags are not trusted as evidence.
+pub fn find_deduction(game: &impl Oracle, is_flagged: impl Fn(usize) -> bool) -> Option<Deduction> {
+    if !game.status().is_ongoing() {
+        return None;
+    }
+
+    let local_deduction = find_local_deductions(&constraints(game), &is_flagged)
+        .into_iter()
+        .map(
+            |(support_tile_ids, target_tile_id, target_is_mine)| Deduction {
+                region_tile_ids: frontier_component(game, target_tile_id),
+                support_tile_ids,
+                target_tile_id,
+                target_is_mine,
+                reasoning: Reasoning::Local,
+            },
+        )
+        .min_by_key(Deduction::rank);
+    if local_deduction.is_some() {
+        return local_deduction;
+    }
+
+    let mut analyzer = Analyzer::new(game.config());
+    analyzer.update_from(game);
+    let partition = analyzer.partition();
+    let mut solved_analyzer = analyzer.clone();
+    solved_analyzer.find_safe_moves(true);
+    let is_target = |tile_id: usize| {
+        let tile = solved_analyzer.get_tile(tile_id);
+        (tile.is_known_safe() || tile.is_known_mine())
+            && game.adjacent_mine_count(tile_id).is_none()
+            && !is_flagged(tile_id)
+    };
+
+    let mut deductions = Vec::new();
+    let mut component_tile_ids = BTreeSet::<usize>::new();
+    for component in &partition.components {
+        component_tile_ids.extend(&component.unknown_tile_ids);
+        if !component.unknown_tile_ids.iter().any(|&id| is_target(id)) {
+            continue;
+        }
+        let arrangements = analyzer
+            .find_possible_mine_arrangements_by_mine_count(component)
+            .into_values()
+            .flatten()
+            .collect_vec();
+        for &tile_id in component
+            .unknown_tile_ids
+            .iter()
+            .filter(|&&id| is_target(id))
+        {
+            let target_is_mine = solved_analyzer.get_tile(tile_id).is_known_mine();
+            let decided_by_component = arrangements
+                .iter()
+                .all(|arrangement| arrangement.binary_search(&tile_id).is_ok() == target_is_mine);
+            deductions.push(Deduction {
+                region_tile_ids: frontier_component(game, tile_id),
+                support_tile_ids: component.number_tile_ids.clone(),
+                target_tile_id: tile_id,
+                target_is_mine,
+                reasoning: if decided_by_component {
+                    Reasoning::Component
+                } else {
+                    Reasoning::MineCount
+                },
+            });
+        }
+    }
+    for tile_id in (0..game.config().grid_config.tile_count()).filter(|&id| is_target(id)) {
+        if component_tile_ids.contains(&tile_id) {
+            continue;
+        }
+        let region_tile_ids = frontier_component(game, tile_id);
+        let deduction = if partition.unconstrained_unknown_tile_ids.contains(&tile_id) {
+            // decided by counting mines, not by any particular numbers
+            Deduction {
+                region_tile_ids,
+                support_tile_ids: BTreeSet::new(),
+                target_tile_id: tile_id,
+                target_is_mine: solved_analyzer.get_tile(tile_id).is_known_mine(),
+                reasoning: Reasoning::MineCount,
+            }
+        } else {
+            // already decided by the analyzer's basic pass, through a chain of numbers too long for the local search
+            Deduction {
+                support_tile_ids: region_tile_ids
+                    .iter()
+                    .copied()
+                    .filter(|&id| game.adjacent_mine_count(id).is_some())
+                    .collect(),
+                region_tile_ids,
+                target_tile_id: tile_id,
+                target_is_mine: solved_analyzer.get_tile(tile_id).is_known_mine(),
+                reasoning: Reasoning::Component,
+            }
+        };
+        deductions.push(deduction);
+    }
+    deductions.into_iter().min_by_key(Deduction::rank)
+}
+
+/// Tracks progressive hints for the current position. Each escalation reveals more about the same cached deduction, so that the levels stay consistent with each other.
+#[derive(Debug, Default)]
+pub struct HintLadder {
+    level: Option<HintLevel>,
+    deduction: Option<Deduction>,
+    /// Used to detect reveals that happened without a call to `reset`
+    hidden_safe_count: usize,
+    /// Set when the last escalation found nothing to hint about
+    found_nothing: bool,
+    deepest_level: Option<HintLevel>,
+}
+
+impl HintLadder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Moves one level up the ladder, finding a deduction first if needed. Returns the new level, or `None` if there is nothing to hint about.
+    pub fn escalate(
+        &mut self,
+        game: &impl Oracle,
+        is_flagged: impl Fn(usize) -> bool,
+    ) -> Option<HintLevel> {
+        if self.deduction.is_some() && game.hidden_safe_count() != self.hidden_safe_count {
+            self.reset();
+        }
+        if self.deduction.is_none() {
+            self.deduction = find_deduction(game, is_flagged);
+            self.found_nothing = self.deduction.is_none();
+            self.deduction.as_ref()?;
+            self.hidden_safe_count = game.hidden_safe_count();
+        }
+        let level = match self.level {
+            None => HintLevel::Region,
+            Some(level) => level.next().unwrap_or(level),
+        };
+        self.level = Some(level);
+        self.deepest_level = self.deepest_level.max(self.level);
+        self.level
+    }
+
+    /// Must be called whenever the board changes (any reveal or flag), so the next hint starts from the bottom of the ladder
+    pub fn reset(&mut self) {
+        self.level = None;
+        self.deduction = None;
+        self.found_nothing = false;
+    }
+
+    /// Whether the last escalation failed because no deduction could be found, meaning a guess is needed
+    pub fn found_nothing(&self) -> bool {
+        self.found_nothing
+    }
+
+    pub fn current(&self) -> Option<(HintLevel, &Deduction)> {
+        self.level.zip(self.deduction.as_ref())
+    }
+
+    /// The deepest level used at any point since this ladder was created, which determines how assisted the game was
+    pub fn deepest_level(&self) -> Option<HintLevel> {
+        self.deepest_level
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::{BoardProvenance, FixedLayoutReason, GameConfig, GameStatus};
+
+    /// A fixed position described row by row: `*` is a hidden mine, `.` is a hidden safe tile, and `o` is a revealed safe tile
+    #[derive(Serialize, Deserialize)]
+    struct FixtureGame {
+        config: GameConfig,
+        mines: Vec<bool>,
+        revealed: Vec<bool>,
+    }
+
+    impl FixtureGame {
+        fn parse(rows: &[&str]) -> Self {
+            let tiles = rows.iter().flat_map(|row| row.chars()).collect_vec();
+            let mines = tiles.iter().map(|&c| c == '*').collect_vec();
+            let grid_config = GridConfig::new(
+                rows.len(),
+                rows[0].len(),
+                mines.iter().filter(|&&is_mine| is_mine).count(),
+            )
+            .unwrap();
+            Self {
+                config: GameConfig {
+                    grid_config,
+                    ..Default::default()
+                },
+                mines,
+                revealed: tiles.iter().map(|&c| c == 'o').collect(),
+            }
+        }
+    }
+
+    impl Oracle for FixtureGame {
+        fn new(_config: GameConfig, _first_click_id: usize) -> Self {
+            unreachable!("fixture games are parsed, not generated")
+        }
+
+        fn config(&self) -> GameConfig {
+            self.config
+        }
+
+        fn provenance(&self) -> BoardProvenance {
+            BoardProvenance::FixedLayout {
+                reason: FixedLayoutReason::Predetermined,
+            }
+        }
+
+        fn adjacent_mine_count(&self, tile_id: usize) -> Option<u8> {
+            self.revealed[tile_id].then(|| {
+                self.config
+                    .grid_config
+                    .iter_adjacent(tile_id)
+                    .filter(|&id| self.mines[id])
+                    .count() as u8
+            })
+        }
+
+        fn iter_adjacent_mine_counts(&self) -> impl Iterator<Item = Option<u8>> + '_ {
+            (0..self.config.grid_config.tile_count()).map(|id| self.adjacent_mine_count(id))
+        }
+
+        fn hidden_safe_count(&self) -> usize {
+            self.mines
+                .iter()
+                .zip(&self.revealed)
+                .filter(|&(&is_mine, &is_revealed)| !is_mine && !is_revealed)
+                .count()
+        }
+
+        fn status(&self) -> GameStatus {
+            GameStatus::Ongoing
+        }
+
+        fn is_mine(&self, tile_id: usize) -> bool {
+            self.mines[tile_id]
+        }
+
+        fn reveal_tile(&mut self, tile_id: usize) {
+            assert!(
+                !self.mines[tile_id],
+                "fixture should only reveal safe tiles"
+            );
+            self.revealed[tile_id] = true;
+        }
+
+        fn chord(&mut self, _number_tile_id: usize, adjacent_hidden_tile_ids: &[usize]) {
+            for &tile_id in adjacent_hidden_tile_ids {
+                self.reveal_tile(tile_id);
+            }
+        }
+    }
+
+    fn assert_consistent(deduction: &Deduction) {
+        assert!(deduction
+            .region_tile_ids
+            .contains(&deduction.target_tile_id));
+        assert!(deduction
+            .support_tile_ids
+            .is_subset(&deduction.region_tile_ids));
+    }
+
+    #[test]
+    fn prefers_smallest_support() {
+        // the 1 in the top left corner touches only one hidden tile, whereas the safe tiles next to the middle 2 need two numbers
+        let game = FixtureGame::parse(&[
+            "o*...", //
+            "oo...", //
+            "o*...", //
+            "oo*..", //
+        ]);
+        let deduction = find_deduction(&game, |_| false).unwrap();
+        assert_consistent(&deduction);
+        assert_eq!(deduction.reasoning, Reasoning::Local);
+        assert_eq!(deduction.support_tile_ids.len(), 1);
+        assert_eq!(deduction.target_tile_id, 1);
+        assert!(deduction.target_is_mine);
+    }
+
+    #[test]
+    fn prefers_safe_tiles_among_equal_support() {
+        // no single number decides anything here, but pairs of numbers decide both the corner tile (safe) and the second mine
+        let game = FixtureGame::parse(&[
+            ".*.*.", //
+            "ooooo", //
+            "ooooo", //
+        ]);
+        let deduction = find_deduction(&game, |_| false).unwrap();
+        assert_consistent(&deduction);
+        assert_eq!(deduction.support_tile_ids.len(), 2);
+        assert!(!deduction.target_is_mine);
+        assert_eq!(deduction.target_tile_id, 0);
+        assert!(find_local_deductions(&constraints(&game), |_| false)
+            .iter()
+            .any(|&(_, tile_id, is_mine)| is_mine && tile_id == 3));
+    }
+
+    #[test]
+    fn skips_flagged_targets() {
+        // once the two mines found by single numbers are flagged, the hint must move on to a safe tile that takes two numbers to find
+        let game = FixtureGame::parse(&[
+            "o*...", //
+            "oo...", //
+            "o*...", //
+            "oo*..", //
+        ]);
+        let flagged_tile_ids = [1, 11];
+        let deduction = find_deduction(&game, |id| flagged_tile_ids.contains(&id)).unwrap();
+        assert_consistent(&deduction);
+        assert_eq!(deduction.reasoning, Reasoning::Local);
+        assert_eq!(deduction.support_tile_ids.len(), 2);
+        assert!(!deduction.target_is_mine);
+        assert!(!game.is_mine(deduction.target_tile_id));
+    }
+
+    #[test]
+    fn reports_when_a_guess_is_needed() {
+        let game = FixtureGame::parse(&[
+            "*...", //
+            "....", //
+            "....", //
+        ]);
+        let mut ladder = HintLadder::new();
+        assert_eq!(ladder.escalate(&game, |_| false), None);
+        assert!(ladder.found_nothing());
+        assert!(ladder.current().is_none());
+        assert_eq!(ladder.deepest_level(), None);
+        ladder.reset();
+        assert!(!ladder.found_nothing());
+    }
+
+    #[test]
+    fn ladder_levels_share_one_deduction() {
+        let game = FixtureGame::parse(&[
+            "o*...", //
+            "oo...", //
+            "o*...", //
+            "oo*..", //
+        ]);
+        let mut ladder = HintLadder::new();
+        let mut deductions = Vec::new();
+        for expected_level in [
+            HintLevel::Region,
+            HintLevel::Support,
+            HintLevel::Target,
+            HintLevel::Explanation,
+            HintLevel::Explanation,
+        ] {
+            assert_eq!(ladder.escalate(&game, |_| false), Some(expected_level));
+            let (level, deduction) = ladder.current().unwrap();
+            assert_eq!(level, expected_level);
+            assert_consistent(deduction);
+            deductions.push(deduction.clone());
+        }
+        assert!(deductions.iter().all_equal());
+        assert_eq!(ladder.deepest_level(), Some(HintLevel::Explanation));
+        assert_eq!(
+            deductions[0].explain(&game),
+            "Every arrangement of mines that satisfies the 1 at row 1, column 1 has a mine at row 1, column 2."
+        );
+    }
+
+    #[test]
+    fn ladder_resets_on_board_change() {
+        let mut game = FixtureGame::parse(&[
+            ".*.*.", //
+            "ooooo", //
+            "ooooo", //
+        ]);
+        let mut ladder = HintLadder::new();
+        ladder.escalate(&game, |_| false);
+        ladder.escalate(&game, |_| false);
+        assert_eq!(ladder.current().unwrap().0, HintLevel::Support);
+
+        // an explicit reset (e.g. after placing a flag) starts over
+        ladder.reset();
+        assert!(ladder.current().is_none());
+        assert_eq!(ladder.escalate(&game, |_| false), Some(HintLevel::Region));
+        ladder.escalate(&game, |_| false);
+
+        // a reveal is detected even without an explicit reset
+        let target_tile_id = ladder.current().unwrap().1.target_tile_id;
+        game.reveal_tile(target_tile_id);
+        assert_eq!(ladder.escalate(&game, |_| false), Some(HintLevel::Region));
+        let deduction = ladder.current().unwrap().1;
+        assert_ne!(deduction.target_tile_id, target_tile_id);
+        assert_consistent(deduction);
+
+        // the deepest level survives resets
+        assert_eq!(ladder.deepest_level(), Some(HintLevel::Support));
+    }
+
+    #[test]
+    fn falls_back_to_the_whole_component() {
+        // no three numbers decide anything here, but the six numbers around the component together leave one tile safe
+        let game = FixtureGame::parse(&[
+            ".....o", //
+            "...oo*", //
+            ".***.o", //
+            "..o.o.", //
+        ]);
+        assert!(find_local_deductions(&constraints(&game), |_| false).is_empty());
+        let deduction = find_deduction(&game, |_| false).unwrap();
+        assert_consistent(&deduction);
+        assert_eq!(deduction.reasoning, Reasoning::Component);
+        assert_eq!(
+            deduction.support_tile_ids,
+            BTreeSet::from([5, 9, 10, 17, 20, 22])
+        );
+        assert_eq!(deduction.target_tile_id, 4);
+        assert!(!deduction.target_is_mine);
+        assert_eq!(
+            deduction.explain(&game),
+            "Every arrangement of mines that satisfies the 6 highlighted numbers leaves row 1, column 5 safe."
+        );
+    }
+
+    #[test]
+    fn falls_back_to_counting_mines() {
+        // the numbers account for every mine, so the tiles next to no number must be safe
+        let game = FixtureGame::parse(&[
+            "o..o*.", //
+            "*...*o", //
+            "o...*o", //
+            ".*.*o.", //
+        ]);
+        assert!(find_local_deductions(&constraints(&game), |_| false).is_empty());
+        let deduction = find_deduction(&game, |_| false).unwrap();
+        assert_consistent(&deduction);
+        assert_eq!(deduction.reasoning, Reasoning::MineCount);
+        assert!(deduction.support_tile_ids.is_empty());
+        assert_eq!(deduction.region_tile_ids, BTreeSet::from([14, 20]));
+        assert_eq!(deduction.target_tile_id, 14);
+        assert!(!deduction.target_is_mine);
+        assert_eq!(
+            deduction.explain(&game),
+            "Every arrangement of mines that satisfies the numbers already uses all 6 mines, so row 3, column 3 must be safe."
+        );
+    }
+
+    #[test]
+    fn falls_back_to_numbers_and_mine_count() {
+        // the three numbers alone leave the target undecided, but together with the total mine count they leave it safe
+        let game = FixtureGame::parse(&[
+            "*o*.o.", //
+            "...*.*", //
+            "*.*.o.", //
+            "o.*o..", //
+        ]);
+        assert!(find_local_deductions(&constraints(&game), |_| false).is_empty());
+        let deduction = find_deduction(&game, |_| false).unwrap();
+        assert_consistent(&deduction);
+        assert_eq!(deduction.reasoning, Reasoning::MineCount);
+        assert_eq!(deduction.support_tile_ids, BTreeSet::from([4, 16, 21]));
+        assert_eq!(deduction.target_tile_id, 17);
+        assert!(!deduction.target_is_mine);
+        assert_eq!(
+            deduction.explain(&game),
+            "Every arrangement of mines that satisfies the 2 at row 1, column 5, the 2 at row 3, column 5 and the 2 at row 4, column 4, along with the total of 7 mines, leaves row 3, column 6 safe."
+        );
+    }
+
+    #[test]
+    fn deductions_are_sound_in_generated_games() {
+        use crate::server::local::LocalGame;
+
+        let config = GameConfig {
+            grid_config: GridConfig::intermediate(),
+            ..Default::default()
+        };
+        let first_click_id = config.grid_config.random_tile_id();
+        let mut game = LocalGame::new(config, first_click_id);
+        game.reveal_tile(first_click_id);
+        while game.status().is_ongoing() {
+            let deduction =
+                find_deduction(&game, |_| false).expect("generated games need no guessing");
+            assert_consistent(&deduction);
+            let mut analyzer = Analyzer::new(config);
+            analyzer.update_from(&game);
+            analyzer.find_safe_moves(true);
+            let analyzer_tile = analyzer.get_tile(deduction.target_tile_id);
+            if deduction.target_is_mine {
+                assert!(analyzer_tile.is_known_mine());
+                // reveal something else instead
+                let safe_tile_id = (0..config.grid_config.tile_count())
+                    .find(|&id| analyzer.get_tile(id).is_known_safe())
+                    .unwrap();
+                game.reveal_tile(safe_tile_id);
+            } else {
+                assert!(analyzer_tile.is_known_safe());
+                game.reveal_tile(deduction.target_tile_id);
+            }
+            assert!(!game.status().is_lost());
+        }
+    }
+}
@@ -1,4 +1,5 @@
 pub mod analyzer;
 pub mod bitset;
+pub mod hint;
 pub mod server;
 pub mod utils;
@@ -7,6 +7,7 @@ use itertools::{chain, izip, repeat_n};
 use num::{BigUint, One};
 use rand::{distributions::WeightedError, seq::SliceRandom};
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 use tinyvec::ArrayVec;
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
@@ -37,6 +38,17 @@ pub struct LocalGame {
     hidden_safe_count: usize,
     status: GameStatus,
     analyzer: Option<Analyzer>,
+    provenance: BoardProvenance,
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum LayoutError {
+    #[error("layout has {actual} mines, but the config calls for {expected}")]
+    MineCountMismatch { expected: usize, actual: usize },
+    #[error("mine tile id {0} is out of bounds")]
+    OutOfBounds(usize),
+    #[error("mine tile id {0} is listed more than once")]
+    Duplicate(usize),
 }
 
 struct SolutionGroup {
@@ -45,6 +57,40 @@ struct SolutionGroup {
 }
 
 impl LocalGame {
+    /// Creates a game with a predetermined mine layout. Unlike `Oracle::new`, this does not guarantee that the game can be won without guessing, and guessing is never punished (see `BoardProvenance::punishes_guessing`).
+    ///
+    /// This includes the first click, which is neither protected nor punished: it reveals exactly what the layout holds there, even if that's a mine.
+    pub fn from_layout(
+        config: GameConfig,
+        mine_tile_ids: &[usize],
+        reason: FixedLayoutReason,
+    ) -> Result<Self, LayoutError> {
+        if mine_tile_ids.len() != config.grid_config.mine_count {
+            return Err(LayoutError::MineCountMismatch {
+                expected: config.grid_config.mine_count,
+                actual: mine_tile_ids.len(),
+            });
+        }
+        let mut tiles = vec![Tile::Hidden { is_mine: false }; config.grid_config.tile_count()];
+        for &mine_tile_id in mine_tile_ids {
+            match tiles.get_mut(mine_tile_id) {
+                None => return Err(LayoutError::OutOfBounds(mine_tile_id)),
+                Some(Tile::Hidden { is_mine: true }) => {
+                    return Err(LayoutError::Duplicate(mine_tile_id))
+                }
+                Some(tile) => *tile = Tile::Hidden { is_mine: true },
+            }
+        }
+        Ok(Self {
+            config,
+            tiles,
+            hidden_safe_count: config.grid_config.safe_count(),
+            status: GameStatus::Ongoing,
+            analyzer: Some(Analyzer::new(config)),
+            provenance: BoardProvenance::FixedLayout { reason },
+        })
+    }
+
     // precondition: tile must be hidden and not a mine
     fn reveal_tile_unchecked(&mut self, tile_id: usize) {
         let mut adjacent_mine_count = 0;
@@ -362,6 +408,7 @@ impl Oracle for LocalGame {
                 status: GameStatus::Ongoing,
                 hidden_safe_count: config.grid_config.safe_count(),
                 analyzer: None,
+                provenance: BoardProvenance::FreshlyGenerated,
             };
             let mut analyzer = Analyzer::new(config);
             game.reveal_tile_unchecked(first_click_id);
@@ -406,6 +453,10 @@ impl Oracle for LocalGame {
         self.config
     }
 
+    fn provenance(&self) -> BoardProvenance {
+        self.provenance
+    }
+
     fn adjacent_mine_count(&self, tile_id: usize) -> Option<u8> {
         self.tiles[tile_id].adjacent_mine_count()
     }
@@ -445,7 +496,9 @@ impl Oracle for LocalGame {
                     self.reveal_tile_unchecked(tile_id);
                     return;
                 };
-                if self.config.punish_guessing && self.punish(tile_id, &mut analyzer) {
+                if self.provenance.punishes_guessing(self.config)
+                    && self.punish(tile_id, &mut analyzer)
+                {
                     self.status = GameStatus::Lost;
                 } else {
                     self.reveal_tile_unchecked(tile_id);
@@ -472,7 +525,7 @@ impl Oracle for LocalGame {
             self.chord_unchecked(adjacent_hidden_tile_ids);
             return;
         };
-        if self.config.punish_guessing
+        if self.provenance.punishes_guessing(self.config)
             && self.punish_chord(number_tile_id, adjacent_hidden_tile_ids, &mut analyzer)
         {
             self.status = GameStatus::Lost;
@@ -513,6 +566,7 @@ impl Oracle for LocalGame {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use strum::IntoEnumIterator;
 
     fn win_all_games(config: GameConfig) {
         let trial_count = 100;
@@ -537,4 +591,120 @@ mod tests {
             ..Default::default()
         })
     }
+
+    /// Every way of constructing a `LocalGame`, along with the provenance it should report. New constructors belong here, so that the test below covers them.
+    fn constructor_registry(config: GameConfig) -> Vec<(LocalGame, BoardProvenance)> {
+        let mine_tile_ids = (0..config.grid_config.mine_count).collect_vec();
+        chain!(
+            [(
+                LocalGame::new(config, config.grid_config.tile_count() - 1),
+                BoardProvenance::FreshlyGenerated
+            )],
+            FixedLayoutReason::iter().map(|reason| (
+                LocalGame::from_layout(config, &mine_tile_ids, reason).unwrap(),
+                BoardProvenance::FixedLayout { reason }
+            ))
+        )
+        .collect()
+    }
+
+    #[test]
+    fn every_constructor_sets_provenance() {
+        let config = GameConfig::default();
+        for (game, provenance) in constructor_registry(config) {
+            assert_eq!(game.provenance(), provenance);
+            assert_eq!(
+                game.provenance().record_bucket(config, None).is_none(),
+                provenance.is_fixed_layout()
+            );
+            // the config is left alone, since fixed layouts are exempt from punishment regardless
+            assert!(game.config().punish_guessing);
+            let game: LocalGame =
+                serde_json::from_str(&serde_json::to_string(&game).unwrap()).unwrap();
+            assert_eq!(game.provenance(), provenance);
+        }
+    }
+
+    fn small_config() -> GameConfig {
+        GameConfig {
+            grid_config: GridConfig::new(3, 4, 1).unwrap(),
+            punish_guessing: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn from_layout_rejects_bad_layouts() {
+        let config = GameConfig {
+            grid_config: GridConfig::new(3, 4, 2).unwrap(),
+            ..Default::default()
+        };
+        assert_eq!(
+            LocalGame::from_layout(config, &[0], FixedLayoutReason::Predetermined).err(),
+            Some(LayoutError::MineCountMismatch {
+                expected: 2,
+                actual: 1
+            })
+        );
+        assert_eq!(
+            LocalGame::from_layout(config, &[0, 12], FixedLayoutReason::Predetermined).err(),
+            Some(LayoutError::OutOfBounds(12))
+        );
+        assert_eq!(
+            LocalGame::from_layout(config, &[3, 3], FixedLayoutReason::Predetermined).err(),
+            Some(LayoutError::Duplicate(3))
+        );
+        assert!(LocalGame::from_layout(config, &[0, 11], FixedLayoutReason::Predetermined).is_ok());
+    }
+
+    /// The same layout as `from_layout`, but claiming to be freshly generated, so that punishment applies
+    fn freshly_generated_layout(config: GameConfig, mine_tile_ids: &[usize]) -> LocalGame {
+        LocalGame {
+            provenance: BoardProvenance::FreshlyGenerated,
+            ..LocalGame::from_layout(config, mine_tile_ids, FixedLayoutReason::Predetermined)
+                .unwrap()
+        }
+    }
+
+    #[test]
+    fn fixed_layout_first_click_is_neither_protected_nor_punished() {
+        let config = small_config();
+        // with no information, the far corner could be the mine
+        let mut control = freshly_generated_layout(config, &[0]);
+        control.reveal_tile(11);
+        assert!(control.status().is_lost());
+
+        let mut game =
+            LocalGame::from_layout(config, &[0], FixedLayoutReason::Predetermined).unwrap();
+        game.reveal_tile(11);
+        assert!(!game.status().is_lost());
+        assert!(game.adjacent_mine_count(11).is_some());
+        assert!(game.config().punish_guessing);
+
+        // the mine stays where the layout put it, even on the first click
+        let mut game =
+            LocalGame::from_layout(config, &[0], FixedLayoutReason::Predetermined).unwrap();
+        game.reveal_tile(0);
+        assert!(game.status().is_lost());
+        assert!(game.is_mine(0));
+    }
+
+    #[test]
+    fn fixed_layout_never_punishes_chord() {
+        let config = small_config();
+        // the 1 in the middle of the left half could have its mine under any of its neighbors
+        let adjacent_hidden_tile_ids = [1, 2, 4, 6, 8, 9, 10];
+
+        let mut control = freshly_generated_layout(config, &[0]);
+        // revealing it normally would be punished, since it's a guess
+        control.reveal_tile_unchecked(5);
+        control.chord(5, &adjacent_hidden_tile_ids);
+        assert!(control.status().is_lost());
+
+        let mut game =
+            LocalGame::from_layout(config, &[0], FixedLayoutReason::Predetermined).unwrap();
+        game.reveal_tile(5);
+        game.chord(5, &adjacent_hidden_tile_ids);
+        assert!(game.status().is_won());
+    }
 }
@@ -1,8 +1,9 @@
-use crate::{analyzer::Analyzer, utils::*};
+use crate::{analyzer::Analyzer, hint::HintLevel, utils::*};
 use itertools::Itertools;
 use rand::Rng;
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use strum::{Display, EnumIter};
 use thiserror::Error;
 
 pub mod local;
@@ -231,11 +232,53 @@ impl Default for GameConfig {
     }
 }
 
+/// Why a game was created with a predetermined mine layout rather than a freshly generated one
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, EnumIter, Display,
+)]
+pub enum FixedLayoutReason {
+    /// The layout was supplied up front, e.g. through `LocalGame::from_layout`
+    #[strum(serialize = "predetermined board")]
+    Predetermined,
+}
+
+/// Whether a game's mine layout was generated for it, or predetermined
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum BoardProvenance {
+    FreshlyGenerated,
+    FixedLayout { reason: FixedLayoutReason },
+}
+
+/// The group of games whose records (such as best times) are compared with each other
+pub type RecordBucket = (GameConfig, Option<HintLevel>);
+
+impl BoardProvenance {
+    pub fn is_fixed_layout(self) -> bool {
+        matches!(self, BoardProvenance::FixedLayout { .. })
+    }
+
+    /// Punishing a guess means rearranging mines, which would change a known layout, so fixed layouts are never punished regardless of the config
+    pub fn punishes_guessing(self, config: GameConfig) -> bool {
+        config.punish_guessing && !self.is_fixed_layout()
+    }
+
+    /// Decides which records a game counts toward, if any. Every record path should go through this, so that games are bucketed consistently. Fixed layouts are excluded, since guessing is not punished on them and the layout may already be known.
+    pub fn record_bucket(
+        self,
+        game_config: GameConfig,
+        hint_depth: Option<HintLevel>,
+    ) -> Option<RecordBucket> {
+        (!self.is_fixed_layout()).then_some((game_config, hint_depth))
+    }
+}
+
 pub trait Oracle: Serialize + for<'a> Deserialize<'a> + 'static {
     fn new(config: GameConfig, first_click_id: usize) -> Self;
 
     fn config(&self) -> GameConfig;
 
+    fn provenance(&self) -> BoardProvenance;
+
     fn adjacent_mine_count(&self, tile_id: usize) -> Option<u8>;
 
     fn iter_adjacent_mine_counts(&self) -> impl Iterator<Item = Option<u8>> + '_;